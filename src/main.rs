@@ -1,9 +1,16 @@
 use async_channel::{bounded, Receiver, Sender};
 use clap::{Parser, Subcommand};
-use std::collections::HashMap;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
 use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::time::{timeout, Duration};
 
 const DEFAULT_PORT_RANGE: &str = "1-65535";
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 3;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -12,38 +19,110 @@ struct Args {
     command: Commands,
 }
 
-#[derive(Subcommand, Debug)]
-enum Commands {
-    /// Brute force an HTTP endpoint
-    Http {
-        /// Path to a file containing raw HTTP request
-        #[arg(short = 'R', long)]
-        raw_request: String,
+/// How payloads from multiple wordlists are combined across injection points,
+/// mirroring Burp Intruder's attack types.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum AttackMode {
+    /// One wordlist at a time, cycled through each position while the others
+    /// stay at their wordlist's first value.
+    Sniper,
+    /// One wordlist, the same value substituted into every position at once.
+    BatteringRam,
+    /// N wordlists stepped through in lockstep (the k-th request gives each
+    /// position the k-th value of its own wordlist, stopping at the shortest).
+    Pitchfork,
+    /// N wordlists combined as a full cartesian product across positions.
+    Clusterbomb,
+}
 
-        /// Status code to be considered a success
-        #[arg(short, long)]
-        status: Option<u16>,
+/// Flags for the `http` subcommand, boxed inside `Commands::Http` so that an
+/// infrequent `PortScan` match doesn't force every `Commands` value to carry
+/// this struct's much larger size around.
+#[derive(clap::Args, Debug)]
+struct HttpArgs {
+    /// Path to a file containing raw HTTP request
+    #[arg(short = 'R', long)]
+    raw_request: String,
+
+    /// Status code to be considered a success
+    #[arg(short, long)]
+    status: Option<u16>,
 
-        /// String to be found in the response body to be considered a success
-        #[arg(short, long)]
-        body: Option<String>,
+    /// String to be found in the response body to be considered a success
+    #[arg(short, long)]
+    body: Option<String>,
 
-        /// String NOT to be found in the response body to be considered a success
-        #[arg(short = 'B', long)]
-        not_body: Option<String>,
+    /// String NOT to be found in the response body to be considered a success
+    #[arg(short = 'B', long)]
+    not_body: Option<String>,
 
-        /// Target to be brute forced
-        #[arg(short, long)]
-        target: String,
+    /// Regex the response body must match to be considered a success
+    #[arg(long)]
+    match_regex: Option<String>,
 
-        /// Path to a file containing list of values to be used
-        #[arg(short, long)]
-        wordlist: String,
+    /// Regex that disqualifies a response if the body matches it
+    #[arg(long)]
+    filter_regex: Option<String>,
 
-        /// Amount of attempts per second
-        #[arg(short, long, default_value_t = 10)]
-        rate: u32,
-    },
+    /// Response body size in bytes required for a match
+    #[arg(long)]
+    match_size: Option<usize>,
+
+    /// Response body size in bytes that disqualifies a response
+    #[arg(long)]
+    filter_size: Option<usize>,
+
+    /// Number of whitespace-separated words the response body must contain for a match
+    #[arg(long)]
+    match_words: Option<usize>,
+
+    /// Number of lines the response body must contain for a match
+    #[arg(long)]
+    match_lines: Option<usize>,
+
+    /// Fire a few nonsense-payload requests first and auto-filter responses matching
+    /// their baseline (status, size, words, lines)
+    #[arg(long, default_value_t = false)]
+    auto_calibrate: bool,
+
+    /// Target to be brute forced
+    #[arg(short, long)]
+    target: String,
+
+    /// Path to a file containing list of values to be used. Pass it multiple times to
+    /// bind one wordlist per injection point (FUZZ1, FUZZ2, ...); with a single
+    /// occurrence the plain FUZZ marker is used instead
+    #[arg(short, long, required = true)]
+    wordlist: Vec<String>,
+
+    /// How multiple wordlists are combined across injection points
+    #[arg(short = 'a', long, value_enum, default_value_t = AttackMode::Sniper)]
+    attack_mode: AttackMode,
+
+    /// Amount of attempts per second
+    #[arg(short, long, default_value_t = 10)]
+    rate: u32,
+
+    /// Number of requests allowed in flight at once, independent of `rate`
+    #[arg(short, long, default_value_t = 10)]
+    concurrency: u32,
+
+    /// Inject a `Range: bytes=0-N` header, merged with any Range already present in the
+    /// raw request (the tighter of the two end bounds wins), and match only against that
+    /// partial body
+    #[arg(long)]
+    range_bytes: Option<u64>,
+
+    /// Maximum number of retries for a transient failure (connect/timeout error or 5xx),
+    /// with exponential backoff and jitter between attempts
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Brute force an HTTP endpoint
+    Http(Box<HttpArgs>),
 
     /// Port scan a host
     PortScan {
@@ -58,6 +137,10 @@ enum Commands {
         /// Range of ports to be scanned
         #[arg(short, long, default_value_t = DEFAULT_PORT_RANGE.to_string())]
         port_range: String,
+
+        /// Seconds to wait for a connection attempt before considering the port filtered
+        #[arg(short = 'T', long, default_value_t = DEFAULT_CONNECT_TIMEOUT_SECS)]
+        connect_timeout: u64,
     },
 }
 
@@ -66,179 +149,855 @@ async fn main() {
     let args = Args::parse();
 
     match args.command {
-        Commands::Http {
-            raw_request,
-            status,
-            body,
-            not_body,
-            target,
-            wordlist,
-            rate,
-        } => http_brute_force(raw_request, status, body, not_body, target, wordlist, rate).await,
+        Commands::Http(args) => {
+            let HttpArgs {
+                raw_request,
+                status,
+                body,
+                not_body,
+                match_regex,
+                filter_regex,
+                match_size,
+                filter_size,
+                match_words,
+                match_lines,
+                auto_calibrate,
+                target,
+                wordlist,
+                attack_mode,
+                rate,
+                concurrency,
+                range_bytes,
+                max_retries,
+            } = *args;
+
+            let criteria = MatchCriteria::new(
+                status,
+                body,
+                not_body,
+                match_regex,
+                filter_regex,
+                match_size,
+                filter_size,
+                match_words,
+                match_lines,
+            );
+
+            let options = RunOptions {
+                attack_mode,
+                rate,
+                concurrency,
+                range_bytes,
+                max_retries,
+            };
+
+            http_brute_force(raw_request, criteria, auto_calibrate, target, wordlist, options).await
+        }
 
         Commands::PortScan {
             target,
             rate,
             port_range,
-        } => port_scan(target, rate, port_range).await,
+            connect_timeout,
+        } => port_scan(target, rate, port_range, connect_timeout).await,
     }
 }
 
-async fn http_brute_force(
-    raw_request_path: String,
+/// Success/filter criteria evaluated against a response. A response is a
+/// success only when every `match_*`/`body`/`status` condition holds and no
+/// `filter_*`/`not_body` condition holds.
+struct MatchCriteria {
     status: Option<u16>,
     body: Option<String>,
     not_body: Option<String>,
-    target: String,
-    wordlist_path: String,
+    match_regex: Option<Regex>,
+    filter_regex: Option<Regex>,
+    match_size: Option<usize>,
+    filter_size: Option<usize>,
+    match_words: Option<usize>,
+    match_lines: Option<usize>,
+}
+
+impl MatchCriteria {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        status: Option<u16>,
+        body: Option<String>,
+        not_body: Option<String>,
+        match_regex: Option<String>,
+        filter_regex: Option<String>,
+        match_size: Option<usize>,
+        filter_size: Option<usize>,
+        match_words: Option<usize>,
+        match_lines: Option<usize>,
+    ) -> Self {
+        MatchCriteria {
+            status,
+            body,
+            not_body,
+            match_regex: match_regex.map(|re| Regex::new(&re).unwrap()),
+            filter_regex: filter_regex.map(|re| Regex::new(&re).unwrap()),
+            match_size,
+            filter_size,
+            match_words,
+            match_lines,
+        }
+    }
+
+    /// Evaluates every body-based condition (status is checked separately by
+    /// the caller before the body is read).
+    fn body_matches(&self, body: &str, size: usize, words: usize, lines: usize) -> bool {
+        if let Some(body_content) = &self.body {
+            if !body.contains(body_content) {
+                return false;
+            }
+        }
+
+        if let Some(not_body_content) = &self.not_body {
+            if body.contains(not_body_content) {
+                return false;
+            }
+        }
+
+        if let Some(re) = &self.match_regex {
+            if !re.is_match(body) {
+                return false;
+            }
+        }
+
+        if let Some(re) = &self.filter_regex {
+            if re.is_match(body) {
+                return false;
+            }
+        }
+
+        if let Some(match_size) = self.match_size {
+            if size != match_size {
+                return false;
+            }
+        }
+
+        if let Some(filter_size) = self.filter_size {
+            if size == filter_size {
+                return false;
+            }
+        }
+
+        if let Some(match_words) = self.match_words {
+            if words != match_words {
+                return false;
+            }
+        }
+
+        if let Some(match_lines) = self.match_lines {
+            if lines != match_lines {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Tunables that shape how requests are generated and sent, independent of
+/// the `MatchCriteria` used to judge responses.
+struct RunOptions {
+    attack_mode: AttackMode,
     rate: u32,
+    concurrency: u32,
+    range_bytes: Option<u64>,
+    max_retries: u32,
+}
+
+async fn http_brute_force(
+    raw_request_path: String,
+    criteria: MatchCriteria,
+    auto_calibrate: bool,
+    target: String,
+    wordlist_paths: Vec<String>,
+    options: RunOptions,
 ) {
-    // Read the contents of the wordlist
-    let mut wordlist_contents = String::new();
-    let mut wordlist_file = tokio::fs::File::open(wordlist_path).await.unwrap();
-    wordlist_file
-        .read_to_string(&mut wordlist_contents)
-        .await
-        .unwrap();
-    let wordlist = wordlist_contents
-        .lines()
-        .map(|l| l.trim_end())
-        .collect::<Vec<_>>();
+    // Read the contents of every wordlist, one per injection point
+    let mut wordlists = Vec::with_capacity(wordlist_paths.len());
+    for wordlist_path in wordlist_paths {
+        let mut wordlist_contents = String::new();
+        let mut wordlist_file = tokio::fs::File::open(wordlist_path).await.unwrap();
+        wordlist_file
+            .read_to_string(&mut wordlist_contents)
+            .await
+            .unwrap();
+        let wordlist = wordlist_contents
+            .lines()
+            .map(|l| l.trim_end().to_string())
+            .collect::<Vec<_>>();
+        wordlists.push(wordlist);
+    }
+
+    assert!(
+        wordlists.iter().all(|w| !w.is_empty()),
+        "each --wordlist file must contain at least one line"
+    );
+
+    // A single wordlist keeps the plain "FUZZ" marker for backwards
+    // compatibility; multiple wordlists are bound to FUZZ1, FUZZ2, ...
+    let markers = if wordlists.len() == 1 {
+        vec!["FUZZ".to_string()]
+    } else {
+        (1..=wordlists.len())
+            .map(|i| format!("FUZZ{i}"))
+            .collect::<Vec<_>>()
+    };
+
+    let payloads = build_payload_combinations(&markers, &wordlists, options.attack_mode);
 
     // Read the raw request from the file
     let mut req_file = tokio::fs::File::open(raw_request_path).await.unwrap();
     let mut raw_request = Vec::new();
     req_file.read_to_end(&mut raw_request).await.unwrap();
 
-    let (req_tx, resp_rx) = rate_limiting_requests(rate);
+    let baseline = if auto_calibrate {
+        Some(calibrate_baseline(&raw_request, &target, &markers, options.range_bytes).await)
+    } else {
+        None
+    };
+
+    let (req_tx, resp_rx) =
+        rate_limiting_requests(options.rate, options.concurrency, options.max_retries);
 
-    let req_count = wordlist.len();
+    let req_count = payloads.len();
     let resp_rx = resp_rx.clone();
     let handle = tokio::spawn(async move {
+        let run_start = std::time::Instant::now();
+        let mut timings = Vec::with_capacity(req_count);
+        let mut successes = 0usize;
+        let mut failures = 0usize;
+        let mut errors = 0usize;
+
         for _ in 0..req_count {
-            let (response_result, word) = resp_rx.recv().await.unwrap();
+            let (response_result, label, timing) = resp_rx.recv().await.unwrap();
+            let gave_up = timing.gave_up;
+            let retried = timing.retried;
+            timings.push(timing);
+
+            if gave_up {
+                errors += 1;
+                let reason = if retried { "gave up after retries" } else { "gave up" };
+                eprintln!("{label}\t\t\t\tERROR ({reason})");
+                continue;
+            }
+
             let response = response_result.unwrap();
+            let status_code = response.status().as_u16();
 
-            if let Some(status) = status {
-                if response.status().as_u16() != status {
-                    eprintln!("{word}\t\t\t\tFAILED");
+            if let Some(status) = criteria.status {
+                if status_code != status {
+                    failures += 1;
+                    eprintln!("{label}\t\t\t\tFAILED");
                     continue;
                 }
             }
 
             let body_bytes = response.bytes().await.unwrap();
             let body_string = String::from_utf8_lossy(&body_bytes);
+            let words = body_string.split_whitespace().count();
+            let lines = body_string.split('\n').count();
 
-            if let Some(body_content) = body.clone() {
-                if !body_string.contains(&body_content) {
-                    eprintln!("{word}\t\t\t\tFAILED");
+            if let Some(baseline) = baseline {
+                if (status_code, body_bytes.len(), words, lines) == baseline {
+                    failures += 1;
+                    eprintln!("{label}\t\t\t\tFAILED");
                     continue;
                 }
             }
 
-            if let Some(not_body_content) = not_body.clone() {
-                if body_string.contains(&not_body_content) {
-                    eprintln!("{word}\t\t\t\tFAILED");
-                    continue;
-                }
+            if !criteria.body_matches(&body_string, body_bytes.len(), words, lines) {
+                failures += 1;
+                eprintln!("{label}\t\t\t\tFAILED");
+                continue;
             }
 
-            eprintln!("{word}\t\t\t\tSUCCESS");
+            successes += 1;
+            eprintln!("{label}\t\t\t\tSUCCESS");
         }
+
+        print_summary(&timings, successes, failures, errors, run_start.elapsed());
     });
 
-    for word in wordlist.into_iter() {
-        // Parse request
-        let mut headers = [httparse::EMPTY_HEADER; 64];
-        let mut req = httparse::Request::new(&mut headers);
+    for payload in payloads.into_iter() {
+        let request = build_request(&raw_request, &target, &payload, options.range_bytes);
+
+        let label = payload
+            .iter()
+            .map(|(marker, value)| format!("{marker}={value}"))
+            .collect::<Vec<_>>()
+            .join(",");
 
-        let raw_request = String::from_utf8_lossy(&raw_request)
-            .trim_end()
-            .replace("FUZZ", word);
-        let raw_request = raw_request.as_bytes();
+        req_tx.send((request, label)).await.unwrap();
+    }
 
-        let bytes_read = req.parse(&raw_request).unwrap().unwrap();
+    handle.await.unwrap();
+}
 
-        // Build reqwest::Request
-        let url = reqwest::Url::parse(&target)
-            .unwrap()
-            .join(req.path.unwrap())
-            .unwrap();
+/// Substitutes `payload`'s marker/value pairs into the raw request template
+/// and parses the result into a `reqwest::Request`.
+fn build_request(
+    raw_request_template: &[u8],
+    target: &str,
+    payload: &[(String, String)],
+    range_bytes: Option<u64>,
+) -> reqwest::Request {
+    let mut headers = [httparse::EMPTY_HEADER; 64];
+    let mut req = httparse::Request::new(&mut headers);
+
+    let mut raw_request = String::from_utf8_lossy(raw_request_template)
+        .trim_end()
+        .to_string();
+
+    // Substitute longest markers first: "FUZZ1" is a prefix of "FUZZ10"/"FUZZ11",
+    // so replacing it first would corrupt every later double-digit marker.
+    let mut payload = payload.to_vec();
+    payload.sort_by_key(|(marker, _)| std::cmp::Reverse(marker.len()));
+    for (marker, value) in &payload {
+        raw_request = raw_request.replace(marker, value);
+    }
+    let raw_request = raw_request.as_bytes();
 
-        let mut request = reqwest::Request::new(req.method.unwrap().try_into().unwrap(), url);
+    let bytes_read = req.parse(raw_request).unwrap().unwrap();
 
-        let mut headers = HashMap::new();
-        for h in req.headers.iter() {
-            headers.insert(
-                h.name.to_string(),
-                String::from_utf8_lossy(h.value).to_string(),
-            );
+    let url = reqwest::Url::parse(target)
+        .unwrap()
+        .join(req.path.unwrap())
+        .unwrap();
+
+    let mut request = reqwest::Request::new(req.method.unwrap().try_into().unwrap(), url);
+
+    let mut headers = HashMap::new();
+    for h in req.headers.iter() {
+        headers.insert(
+            h.name.to_string(),
+            String::from_utf8_lossy(h.value).to_string(),
+        );
+    }
+
+    if let Some(range_bytes) = range_bytes {
+        let existing_key = headers
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case("range"))
+            .cloned();
+
+        let merged = merge_range_header(existing_key.as_ref().map(|k| headers[k].as_str()), range_bytes);
+        headers.insert(existing_key.unwrap_or_else(|| "Range".to_string()), merged);
+    }
+
+    let headers: reqwest::header::HeaderMap = (&headers).try_into().unwrap();
+    request.headers_mut().extend(headers);
+
+    let request_body = request.body_mut();
+    let body_bytes = raw_request[bytes_read..].to_vec().into();
+    *request_body = Some(body_bytes);
+
+    request
+}
+
+/// Merges `range_bytes` into an existing `Range` header value (if any),
+/// taking the tighter of the two end bounds rather than overriding it
+/// outright. With no existing header, produces a plain `bytes=0-N`.
+fn merge_range_header(existing: Option<&str>, range_bytes: u64) -> String {
+    let Some(existing) = existing.and_then(|v| v.strip_prefix("bytes=")) else {
+        return format!("bytes=0-{range_bytes}");
+    };
+
+    let (start, end) = existing.split_once('-').unwrap_or((existing, ""));
+    let start = start.trim();
+    let end = end.trim().parse::<u64>().unwrap_or(range_bytes).min(range_bytes);
+
+    format!("bytes={start}-{end}")
+}
+
+/// Builds the shared `reqwest::Client` used for both the main fuzzing loop
+/// and baseline calibration, with transparent response decompression so body
+/// matchers see decoded text instead of raw gzip/deflate/brotli bytes.
+fn build_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .gzip(true)
+        .deflate(true)
+        .brotli(true)
+        .build()
+        .unwrap()
+}
+
+/// Fires a handful of requests with random nonsense payloads and returns the
+/// most common `(status, size, words, lines)` signature among their
+/// responses, used to auto-filter wildcard/soft-404 noise.
+async fn calibrate_baseline(
+    raw_request: &[u8],
+    target: &str,
+    markers: &[String],
+    range_bytes: Option<u64>,
+) -> (u16, usize, usize, usize) {
+    const CALIBRATION_REQUESTS: u32 = 3;
+
+    let client = build_client();
+    let mut signatures = HashMap::new();
+
+    for _ in 0..CALIBRATION_REQUESTS {
+        let payload = markers
+            .iter()
+            .map(|marker| (marker.clone(), random_word()))
+            .collect::<Vec<_>>();
+
+        let request = build_request(raw_request, target, &payload, range_bytes);
+        let response = client.execute(request).await.unwrap();
+        let status = response.status().as_u16();
+        let body_bytes = response.bytes().await.unwrap();
+        let body_string = String::from_utf8_lossy(&body_bytes);
+
+        let signature = (
+            status,
+            body_bytes.len(),
+            body_string.split_whitespace().count(),
+            body_string.split('\n').count(),
+        );
+        *signatures.entry(signature).or_insert(0u32) += 1;
+    }
+
+    signatures
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(signature, _)| signature)
+        .expect("auto-calibration must send at least one request")
+}
+
+/// A random alphanumeric value used as a nonsense payload for calibration.
+fn random_word() -> String {
+    use rand::distributions::Alphanumeric;
+    use rand::Rng;
+
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(8)
+        .map(char::from)
+        .collect()
+}
+
+/// Builds the list of payload combinations to send, one `Vec<(marker, value)>`
+/// per request, according to the selected `AttackMode`. `markers[i]`
+/// corresponds to `wordlists[i]`.
+fn build_payload_combinations(
+    markers: &[String],
+    wordlists: &[Vec<String>],
+    attack_mode: AttackMode,
+) -> Vec<Vec<(String, String)>> {
+    match attack_mode {
+        AttackMode::Sniper => {
+            let mut combinations = Vec::new();
+            for (position, wordlist) in wordlists.iter().enumerate() {
+                for value in wordlist {
+                    let combination = markers
+                        .iter()
+                        .enumerate()
+                        .map(|(i, marker)| {
+                            let value = if i == position {
+                                value.clone()
+                            } else {
+                                wordlists[i][0].clone()
+                            };
+                            (marker.clone(), value)
+                        })
+                        .collect();
+                    combinations.push(combination);
+                }
+            }
+            combinations
         }
-        let headers: reqwest::header::HeaderMap = (&headers).try_into().unwrap();
-        request.headers_mut().extend(headers);
 
-        let request_body = request.body_mut();
-        let body = raw_request[bytes_read..].to_vec().into();
-        *request_body = Some(body);
+        AttackMode::BatteringRam => wordlists[0]
+            .iter()
+            .map(|value| {
+                markers
+                    .iter()
+                    .map(|marker| (marker.clone(), value.clone()))
+                    .collect()
+            })
+            .collect(),
+
+        AttackMode::Pitchfork => {
+            let len = wordlists.iter().map(|w| w.len()).min().unwrap_or(0);
+            (0..len)
+                .map(|i| {
+                    markers
+                        .iter()
+                        .zip(wordlists.iter())
+                        .map(|(marker, wordlist)| (marker.clone(), wordlist[i].clone()))
+                        .collect()
+                })
+                .collect()
+        }
 
-        req_tx.send((request, word.to_string())).await.unwrap();
+        AttackMode::Clusterbomb => {
+            let mut combinations = vec![Vec::new()];
+            for (marker, wordlist) in markers.iter().zip(wordlists.iter()) {
+                let mut next = Vec::with_capacity(combinations.len() * wordlist.len());
+                for combination in &combinations {
+                    for value in wordlist {
+                        let mut combination = combination.clone();
+                        combination.push((marker.clone(), value.clone()));
+                        next.push(combination);
+                    }
+                }
+                combinations = next;
+            }
+            combinations
+        }
+    }
+}
+
+/// Outcome of a single TCP-connect attempt against a port.
+enum PortState {
+    Open,
+    Closed,
+    Filtered,
+}
+
+impl std::fmt::Display for PortState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortState::Open => write!(f, "open"),
+            PortState::Closed => write!(f, "closed"),
+            PortState::Filtered => write!(f, "filtered"),
+        }
+    }
+}
+
+async fn port_scan(target: String, rate: u32, port_range: String, connect_timeout: u64) {
+    let ports = parse_port_range(&port_range);
+    let ip = resolve_target(&target).await;
+    let connect_timeout = Duration::from_secs(connect_timeout);
+
+    let (scan_tx, result_rx) = rate_limiting_port_scans(rate, connect_timeout);
+
+    let port_count = ports.len();
+    let handle = tokio::spawn(async move {
+        for _ in 0..port_count {
+            let (port, state) = result_rx.recv().await.unwrap();
+            println!("{port}\t\t\t\t{state}");
+        }
+    });
+
+    for port in ports {
+        let addr = SocketAddr::new(ip, port);
+        scan_tx.send((addr, port)).await.unwrap();
     }
 
     handle.await.unwrap();
 }
 
-async fn port_scan(target: String, rate: u32, port_range: String) {
-    println!("PortScan");
-    println!("\ttarget: {}", target);
-    println!("\trate: {}", rate);
-    println!("\tport_range: {}", port_range);
+/// Resolves `target` to a single `IpAddr`, reused for every port so the scan
+/// doesn't re-resolve DNS once per connection attempt.
+async fn resolve_target(target: &str) -> IpAddr {
+    tokio::net::lookup_host((target, 0))
+        .await
+        .unwrap()
+        .next()
+        .expect("target did not resolve to any address")
+        .ip()
+}
+
+/// Parses a port range like `1-65535`, `80,443,8080` or a mix of both
+/// (`22,80-90,443`) into the list of ports to scan.
+fn parse_port_range(port_range: &str) -> Vec<u16> {
+    let mut ports = Vec::new();
+
+    for part in port_range.split(',') {
+        let part = part.trim();
+
+        if let Some((start, end)) = part.split_once('-') {
+            let start: u16 = start.trim().parse().unwrap();
+            let end: u16 = end.trim().parse().unwrap();
+            ports.extend(start..=end);
+        } else {
+            ports.push(part.parse().unwrap());
+        }
+    }
+
+    ports
+}
+
+fn rate_limiting_port_scans(
+    reqs_per_sec: u32,
+    connect_timeout: Duration,
+) -> (Sender<(SocketAddr, u16)>, Receiver<(u16, PortState)>) {
+    let (scan_tx, scan_rx) = bounded::<(SocketAddr, u16)>(1);
+    let (result_tx, result_rx) = bounded::<(u16, PortState)>(1);
+
+    for _ in 0..reqs_per_sec {
+        let scan_rx = scan_rx.clone();
+        let result_tx = result_tx.clone();
+
+        tokio::spawn(async move {
+            let mut last_attempt = std::time::Instant::now()
+                .checked_sub(std::time::Duration::from_secs(1))
+                .unwrap();
+
+            while let Ok((addr, port)) = scan_rx.recv().await {
+                let time_since_last_attempt = last_attempt.elapsed().as_millis();
+                if time_since_last_attempt < 1000 {
+                    let remaining_waiting_time = 1000 - time_since_last_attempt as u64;
+                    let sleep_time = std::time::Duration::from_millis(remaining_waiting_time);
+                    tokio::time::sleep(sleep_time).await;
+                }
+
+                let state = match timeout(connect_timeout, TcpStream::connect(addr)).await {
+                    Ok(Ok(_stream)) => PortState::Open,
+                    Ok(Err(_)) => PortState::Closed,
+                    Err(_) => PortState::Filtered,
+                };
+                last_attempt = std::time::Instant::now();
+
+                result_tx.send((port, state)).await.unwrap();
+            }
+        });
+    }
+
+    (scan_tx, result_rx)
+}
+
+/// Timing and size metadata for a single request, captured alongside its
+/// response so it survives past the worker that made the request. `status`
+/// and `len_bytes` are best-effort: `None`/`0` when the request errored or
+/// the response didn't carry a `Content-Length`.
+#[derive(Debug, Clone)]
+struct RequestResult {
+    start: std::time::Instant,
+    end: std::time::Instant,
+    status: Option<u16>,
+    len_bytes: usize,
+    /// Set when the final outcome is still a transient failure (connect/timeout
+    /// error or 5xx) or any other error, as opposed to a clean response that
+    /// simply didn't match.
+    gave_up: bool,
+    /// Whether at least one retry was actually attempted before `gave_up` was
+    /// decided, so callers can tell "failed after retrying" apart from
+    /// "failed on the first and only attempt" (e.g. `--max-retries 0`, or a
+    /// non-retryable error).
+    retried: bool,
+}
+
+/// Prints a latency/throughput summary for a finished run: counts, the
+/// achieved requests/sec, latency percentiles, a status-code distribution
+/// and a text histogram of response times, the way HTTP load generators
+/// summarize a run.
+fn print_summary(
+    timings: &[RequestResult],
+    successes: usize,
+    failures: usize,
+    errors: usize,
+    elapsed: std::time::Duration,
+) {
+    if timings.is_empty() {
+        return;
+    }
+
+    let mut latencies = timings
+        .iter()
+        .map(|t| t.end.duration_since(t.start))
+        .collect::<Vec<_>>();
+    latencies.sort();
+
+    let total = timings.len();
+    let requests_per_sec = total as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+    let sum: std::time::Duration = latencies.iter().sum();
+    let mean = sum / total as u32;
+
+    let total_bytes: usize = timings.iter().map(|t| t.len_bytes).sum();
+    let bytes_per_sec = total_bytes as f64 / elapsed.as_secs_f64().max(f64::EPSILON);
+
+    let retried_errors = timings.iter().filter(|t| t.gave_up && t.retried).count();
+
+    let percentile = |p: f64| -> std::time::Duration {
+        let index = ((p * (latencies.len() - 1) as f64).round() as usize).min(latencies.len() - 1);
+        latencies[index]
+    };
+
+    let mut status_counts = BTreeMap::new();
+    for timing in timings {
+        let key = match timing.status {
+            Some(status) => status.to_string(),
+            None => "ERROR".to_string(),
+        };
+        *status_counts.entry(key).or_insert(0usize) += 1;
+    }
+
+    println!();
+    println!("Summary");
+    println!("\trequests:     {total}");
+    println!("\tsuccesses:    {successes}");
+    println!("\tfailures:     {failures}");
+    println!("\terrors:       {errors} ({retried_errors} gave up after retries, {} failed outright)", errors - retried_errors);
+    println!("\treqs/sec:     {requests_per_sec:.2}");
+    println!("\tbytes:        {total_bytes} ({bytes_per_sec:.2}/sec)");
+    println!("\tmin latency:  {:?}", latencies.first().unwrap());
+    println!("\tmean latency: {mean:?}");
+    println!("\tp50 latency:  {:?}", percentile(0.50));
+    println!("\tp90 latency:  {:?}", percentile(0.90));
+    println!("\tp99 latency:  {:?}", percentile(0.99));
+    println!("\tmax latency:  {:?}", latencies.last().unwrap());
+
+    println!("\tstatus codes:");
+    for (status, count) in &status_counts {
+        println!("\t\t{status}: {count}");
+    }
+
+    println!("\tlatency histogram:");
+    print_histogram(&latencies);
+}
+
+/// Buckets `latencies` into a handful of equal-width ranges and renders a
+/// `#`-bar for each, scaled relative to the busiest bucket.
+fn print_histogram(latencies: &[std::time::Duration]) {
+    const BUCKETS: usize = 10;
+    const BAR_WIDTH: usize = 40;
+
+    let min = latencies.first().unwrap().as_secs_f64();
+    let max = latencies.last().unwrap().as_secs_f64();
+    let bucket_width = ((max - min) / BUCKETS as f64).max(f64::EPSILON);
+
+    let mut counts = [0usize; BUCKETS];
+    for latency in latencies {
+        let bucket = (((latency.as_secs_f64() - min) / bucket_width) as usize).min(BUCKETS - 1);
+        counts[bucket] += 1;
+    }
+
+    let max_count = *counts.iter().max().unwrap_or(&0);
+
+    for (i, count) in counts.iter().enumerate() {
+        let low = min + i as f64 * bucket_width;
+        let high = low + bucket_width;
+        let bar_len = (count * BAR_WIDTH).checked_div(max_count).unwrap_or(0);
+        let bar = "#".repeat(bar_len);
+        println!("\t\t{low:>8.3}s - {high:>8.3}s | {bar:<BAR_WIDTH$} {count}");
+    }
+}
+
+/// A shared token bucket capped at, and refilled at, `reqs_per_sec` tokens
+/// per second. Acquiring a token caps how many requests *start* per second;
+/// it's independent of how many run concurrently, which is controlled by how
+/// many workers pull from the bucket.
+fn token_bucket(reqs_per_sec: u32) -> Arc<Semaphore> {
+    let reqs_per_sec = reqs_per_sec.max(1);
+    let bucket = Arc::new(Semaphore::new(reqs_per_sec as usize));
+
+    let refill_bucket = bucket.clone();
+    tokio::spawn(async move {
+        let interval = Duration::from_secs_f64(1.0 / reqs_per_sec as f64);
+        loop {
+            tokio::time::sleep(interval).await;
+            if refill_bucket.available_permits() < reqs_per_sec as usize {
+                refill_bucket.add_permits(1);
+            }
+        }
+    });
+
+    bucket
+}
+
+/// Base delay for the first retry; doubles on every subsequent attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+/// Upper bound on a single retry delay, regardless of attempt number.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Whether `result` represents a transient failure worth retrying: a
+/// connect/timeout error, or a 5xx response. A clean 4xx (or any other
+/// non-retryable error, e.g. a decode or builder error) is returned as-is.
+fn is_retryable(result: &Result<reqwest::Response, reqwest::Error>) -> bool {
+    match result {
+        Ok(response) => response.status().is_server_error(),
+        Err(e) => e.is_connect() || e.is_timeout(),
+    }
+}
+
+/// Exponential backoff (`RETRY_BASE_DELAY * 2^attempt`, capped at
+/// `RETRY_MAX_DELAY`) with +/-50% jitter so retries from many workers don't
+/// land in lockstep.
+fn backoff_with_jitter(attempt: u32) -> Duration {
+    use rand::Rng;
+
+    let exponential = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(RETRY_MAX_DELAY);
+
+    let jitter_factor = rand::thread_rng().gen_range(0.5..=1.5);
+    Duration::from_secs_f64(exponential.as_secs_f64() * jitter_factor).min(RETRY_MAX_DELAY)
 }
 
 fn rate_limiting_requests(
     reqs_per_sec: u32,
+    concurrency: u32,
+    max_retries: u32,
 ) -> (
     Sender<(reqwest::Request, String)>,
-    Receiver<(Result<reqwest::Response, reqwest::Error>, String)>,
+    Receiver<(Result<reqwest::Response, reqwest::Error>, String, RequestResult)>,
 ) {
     let (request_tx, request_rx) = bounded::<(reqwest::Request, String)>(1);
-    let (responses_tx, responses_rx) =
-        bounded::<(Result<reqwest::Response, reqwest::Error>, String)>(1);
+    let (responses_tx, responses_rx) = bounded::<(
+        Result<reqwest::Response, reqwest::Error>,
+        String,
+        RequestResult,
+    )>(1);
 
-    let client = reqwest::Client::new();
+    let client = build_client();
+    let bucket = token_bucket(reqs_per_sec);
 
-    for _ in 0..reqs_per_sec {
+    for _ in 0..concurrency.max(1) {
         let request_rx = request_rx.clone();
         let responses_tx = responses_tx.clone();
         let client = client.clone();
+        let bucket = bucket.clone();
 
         tokio::spawn(async move {
-            let mut last_request = std::time::Instant::now()
-                .checked_sub(std::time::Duration::from_secs(1))
-                .unwrap();
-
             while let Ok((request, word)) = request_rx.recv().await {
-                let time_since_last_request = last_request.elapsed().as_millis();
-                if time_since_last_request < 1000 {
-                    let remaining_waiting_time = 1000 - time_since_last_request as u64;
-                    let sleep_time = std::time::Duration::from_millis(remaining_waiting_time);
-                    tokio::time::sleep(sleep_time).await;
-                }
+                bucket.acquire().await.unwrap().forget();
 
-                // make the request
+                // make the request, retrying transient failures with backoff. `start`
+                // is reset before each attempt so the recorded latency reflects only
+                // the attempt that actually produced `result`, not time spent asleep
+                // in backoff between retries.
+                let mut start = std::time::Instant::now();
                 let req = request.try_clone().unwrap();
                 let mut result = client.execute(req).await;
-                for _retry in 0..3 {
-                    if result.is_ok() {
-                        break;
-                    }
 
-                    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+                let mut attempt = 0;
+                let mut retried = false;
+                while attempt < max_retries && is_retryable(&result) {
+                    tokio::time::sleep(backoff_with_jitter(attempt)).await;
+
+                    attempt += 1;
+                    retried = true;
+                    start = std::time::Instant::now();
                     let req = request.try_clone().unwrap();
                     result = client.execute(req).await;
                 }
-                last_request = std::time::Instant::now();
 
-                responses_tx.send((result, word)).await.unwrap();
+                let end = std::time::Instant::now();
+                let gave_up = match &result {
+                    Ok(response) => response.status().is_server_error(),
+                    Err(_) => true,
+                };
+
+                let timing = RequestResult {
+                    start,
+                    end,
+                    status: result.as_ref().ok().map(|r| r.status().as_u16()),
+                    len_bytes: result
+                        .as_ref()
+                        .ok()
+                        .and_then(|r| r.content_length())
+                        .unwrap_or(0) as usize,
+                    gave_up,
+                    retried,
+                };
+
+                responses_tx.send((result, word, timing)).await.unwrap();
             }
         });
     }